@@ -1,7 +1,9 @@
 use crate::core::{
     ChildProcessIdMap, ChildProcessIdMapFilter, Config, KillOutput, Killable, Output, Outputs,
-    ProcessId, ProcessIds, ProcessInfo, ProcessInfoMap, ProcessInfos, Result,
+    ProcessId, ProcessIds, ProcessInfo, ProcessInfoMap, ProcessInfos, ProcessStatus, Result,
+    Signal,
 };
+use std::time::{Duration, Instant};
 use tracing::debug;
 
 #[cfg(target_os = "linux")]
@@ -44,15 +46,33 @@ pub(crate) fn get_process_info_map(process_infos: ProcessInfos) -> ProcessInfoMa
 }
 
 /// Breadth-first search to get all process ids to kill.
+///
+/// When `config.skip_zombies` is set, a process already in
+/// [`ProcessStatus::Zombie`] or [`ProcessStatus::Dead`] state is left out of
+/// the result, but its children are still walked and considered normally.
 pub(crate) fn get_process_ids_to_kill(
     target_process_id: ProcessId,
     child_process_id_map: &ChildProcessIdMap,
     config: &Config,
+    process_info_map: &ProcessInfoMap,
 ) -> ProcessIds {
     let mut process_ids_to_kill = Vec::new();
     let mut queue = std::collections::VecDeque::new();
     queue.push_back(target_process_id);
     while let Some(process_id) = queue.pop_front() {
+        if let Some(children) = child_process_id_map.get(&process_id) {
+            for &child in children {
+                queue.push_back(child);
+            }
+        }
+        if config.skip_zombies
+            && process_info_map.get(&process_id).is_some_and(|info| {
+                matches!(info.status, ProcessStatus::Zombie | ProcessStatus::Dead)
+            })
+        {
+            debug!(process_id, "Skipping zombie process id");
+            continue;
+        }
         if process_id == target_process_id {
             if config.include_target {
                 process_ids_to_kill.push(process_id);
@@ -66,13 +86,60 @@ pub(crate) fn get_process_ids_to_kill(
         } else {
             process_ids_to_kill.push(process_id);
         }
-        if let Some(children) = child_process_id_map.get(&process_id) {
-            for &child in children {
-                queue.push_back(child);
+    }
+    process_ids_to_kill
+}
+
+/// Like [`get_process_ids_to_kill`], but groups the result by generation
+/// (distance from `target_process_id`) instead of flattening it.
+///
+/// Used by the concurrent tokio backend, which needs to kill every process
+/// in one generation before moving on to a shallower one (preserving
+/// "children before parents") while still running within a generation
+/// fully in parallel.
+pub(crate) fn get_process_id_generations(
+    target_process_id: ProcessId,
+    child_process_id_map: &ChildProcessIdMap,
+    config: &Config,
+    process_info_map: &ProcessInfoMap,
+) -> Vec<ProcessIds> {
+    let mut generations = Vec::new();
+    let mut current_generation = vec![target_process_id];
+    while !current_generation.is_empty() {
+        let mut next_generation = ProcessIds::new();
+        let mut kept = ProcessIds::new();
+        for process_id in current_generation {
+            if let Some(children) = child_process_id_map.get(&process_id) {
+                next_generation.extend(children.iter().copied());
+            }
+            if config.skip_zombies
+                && process_info_map.get(&process_id).is_some_and(|info| {
+                    matches!(info.status, ProcessStatus::Zombie | ProcessStatus::Dead)
+                })
+            {
+                debug!(process_id, "Skipping zombie process id");
+                continue;
+            }
+            if process_id == target_process_id {
+                if config.include_target {
+                    kept.push(process_id);
+                } else {
+                    debug!(
+                        process_id,
+                        include_target = config.include_target,
+                        "Skipping target process id"
+                    );
+                }
+            } else {
+                kept.push(process_id);
             }
         }
+        if !kept.is_empty() {
+            generations.push(kept);
+        }
+        current_generation = next_generation;
     }
-    process_ids_to_kill
+    generations
 }
 
 pub(crate) fn parse_kill_output(
@@ -90,8 +157,19 @@ pub(crate) fn parse_kill_output(
                 process_id: process_info.process_id,
                 parent_process_id: process_info.parent_process_id,
                 name: process_info.name,
+                status: process_info.status,
+                uid: process_info.uid,
+                gid: process_info.gid,
+                cmdline: process_info.cmdline,
             })
         }
+        KillOutput::Reaped { process_id } => {
+            if process_info_map.remove(&process_id).is_none() {
+                debug!(process_id, "Process info not found");
+                return None;
+            }
+            Some(Output::Reaped { process_id })
+        }
         KillOutput::MaybeAlreadyTerminated { process_id, source } => {
             Some(Output::MaybeAlreadyTerminated { process_id, source })
         }
@@ -105,19 +183,232 @@ pub(crate) fn kill_tree_internal(
 ) -> Result<Outputs> {
     let child_process_id_map =
         crate::common::get_child_process_id_map(&process_infos, imp::child_process_id_map_filter);
-    let process_ids_to_kill =
-        crate::common::get_process_ids_to_kill(process_id, &child_process_id_map, config);
-    let killer = imp::new_killer(config)?;
-    let mut outputs = Outputs::new();
     let mut process_info_map = crate::common::get_process_info_map(process_infos);
-    // kill children first
+    let process_ids_to_kill = crate::common::get_process_ids_to_kill(
+        process_id,
+        &child_process_id_map,
+        config,
+        &process_info_map,
+    );
+    let killer = imp::new_killer(config, &process_ids_to_kill)?;
+    let mut outputs = Outputs::new();
+
+    if config.use_process_groups {
+        kill_process_groups(&process_ids_to_kill, config.signal);
+    }
+
+    if let Some(escalation_timeout) = config.escalation_timeout {
+        crate::common::kill_with_escalation(
+            &killer,
+            &process_ids_to_kill,
+            config.signal,
+            escalation_timeout,
+            &mut process_info_map,
+            &mut outputs,
+        )?;
+    } else {
+        // kill children first
+        for &process_id in process_ids_to_kill.iter().rev() {
+            let expected_start_time = process_info_map.get(&process_id).map(|i| i.start_time);
+            let kill_output = killer.kill(process_id, config.signal, expected_start_time)?;
+            let Some(output) =
+                crate::common::parse_kill_output(kill_output, &mut process_info_map)
+            else {
+                continue;
+            };
+            outputs.push(output);
+        }
+    }
+    Ok(outputs)
+}
+
+/// Best-effort sweep for `Config::use_process_groups`: signal every unique
+/// process group among `process_ids_to_kill`'s members, catching
+/// grandchildren that double-forked and reparented to init before the
+/// snapshot was taken and so fell outside the enumerated tree. No-op on
+/// platforms without process groups.
+///
+/// This is a single fire-and-forget signal with no `Output` of its own and
+/// no escalation: unlike `process_ids_to_kill`'s members, a process reached
+/// only via its group is not re-signalled with [`Signal::Sigkill`] if it
+/// ignores the first one.
+#[cfg(unix)]
+pub(crate) fn kill_process_groups(process_ids_to_kill: &[ProcessId], signal: Signal) {
+    let mut signalled = std::collections::HashSet::new();
+    for &process_id in process_ids_to_kill {
+        let Some(process_group_id) = imp::get_process_group_id(process_id) else {
+            continue;
+        };
+        if !signalled.insert(process_group_id) {
+            continue;
+        }
+        if let Err(error) = crate::unix::killpg(process_group_id, signal) {
+            debug!(process_group_id, error = ?error, "failed to signal process group");
+        }
+    }
+}
+
+#[cfg(not(unix))]
+pub(crate) fn kill_process_groups(_process_ids_to_kill: &[ProcessId], _signal: Signal) {}
+
+/// Send `signal` to every process in `process_ids_to_kill`, wait up to
+/// `escalation_timeout` for them to exit, then re-send [`Signal::Sigkill`]
+/// to whichever are still alive.
+///
+/// When `signal` is already [`Signal::Sigkill`], it is sent once and the
+/// wait and re-send are skipped, since there is nothing further to
+/// escalate to.
+pub(crate) fn kill_with_escalation(
+    killer: &impl Killable,
+    process_ids_to_kill: &[ProcessId],
+    signal: Signal,
+    escalation_timeout: Duration,
+    process_info_map: &mut ProcessInfoMap,
+    outputs: &mut Outputs,
+) -> Result<()> {
+    // kill children first, keeping the real result of each so we never have
+    // to guess at (or fabricate) what actually happened to a given pid
+    let mut first_round = std::collections::HashMap::new();
     for &process_id in process_ids_to_kill.iter().rev() {
-        let kill_output = killer.kill(process_id)?;
-        let Some(output) = crate::common::parse_kill_output(kill_output, &mut process_info_map)
-        else {
+        let expected_start_time = process_info_map.get(&process_id).map(|i| i.start_time);
+        let kill_output = killer.kill(process_id, signal, expected_start_time)?;
+        first_round.insert(process_id, kill_output);
+    }
+
+    if signal == Signal::Sigkill {
+        for &process_id in process_ids_to_kill.iter().rev() {
+            let Some(kill_output) = first_round.remove(&process_id) else {
+                continue;
+            };
+            let Some(output) = crate::common::parse_kill_output(kill_output, process_info_map)
+            else {
+                continue;
+            };
+            outputs.push(output);
+        }
+        return Ok(());
+    }
+
+    // only processes we actually signalled successfully need watching;
+    // anything already reported as reaped or maybe-already-terminated has
+    // nothing left to escalate
+    let deadline = Instant::now() + escalation_timeout;
+    let mut survivors: std::collections::HashSet<ProcessId> = first_round
+        .iter()
+        .filter(|(_, kill_output)| matches!(kill_output, KillOutput::Killed { .. }))
+        .map(|(&process_id, _)| process_id)
+        .collect();
+    while !survivors.is_empty() && Instant::now() < deadline {
+        survivors.retain(|&process_id| imp::is_alive(process_id));
+        if survivors.is_empty() {
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    }
+
+    for &process_id in process_ids_to_kill.iter().rev() {
+        let kill_output = if survivors.contains(&process_id) {
+            let expected_start_time = process_info_map.get(&process_id).map(|i| i.start_time);
+            killer.kill(process_id, Signal::Sigkill, expected_start_time)?
+        } else {
+            let Some(kill_output) = first_round.remove(&process_id) else {
+                continue;
+            };
+            kill_output
+        };
+        let Some(output) = crate::common::parse_kill_output(kill_output, process_info_map) else {
             continue;
         };
         outputs.push(output);
     }
-    Ok(outputs)
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process_info(process_id: ProcessId, status: ProcessStatus) -> ProcessInfo {
+        ProcessInfo {
+            process_id,
+            parent_process_id: 0,
+            name: String::new(),
+            start_time: 0,
+            status,
+            uid: 0,
+            gid: 0,
+            cmdline: None,
+        }
+    }
+
+    fn config(skip_zombies: bool) -> Config {
+        Config {
+            include_target: false,
+            skip_zombies,
+            ..Config::default()
+        }
+    }
+
+    // Tree: 1 -> [2, 3], 3 -> [4]. 3 is a zombie.
+    fn child_process_id_map() -> ChildProcessIdMap {
+        ChildProcessIdMap::from([(1, vec![2, 3]), (3, vec![4])])
+    }
+
+    fn process_info_map() -> ProcessInfoMap {
+        ProcessInfoMap::from([
+            (1, process_info(1, ProcessStatus::Run)),
+            (2, process_info(2, ProcessStatus::Run)),
+            (3, process_info(3, ProcessStatus::Zombie)),
+            (4, process_info(4, ProcessStatus::Run)),
+        ])
+    }
+
+    #[test]
+    fn get_process_ids_to_kill_keeps_zombies_by_default() {
+        let process_ids = get_process_ids_to_kill(
+            1,
+            &child_process_id_map(),
+            &config(false),
+            &process_info_map(),
+        );
+        assert_eq!(process_ids, vec![2, 3, 4]);
+    }
+
+    #[test]
+    fn get_process_ids_to_kill_skips_zombies_but_still_walks_their_children() {
+        let process_ids = get_process_ids_to_kill(
+            1,
+            &child_process_id_map(),
+            &config(true),
+            &process_info_map(),
+        );
+        // 3 is skipped, but its sibling 2 and its own child 4 are still present.
+        assert_eq!(process_ids, vec![2, 4]);
+    }
+
+    #[test]
+    fn get_process_id_generations_groups_children_before_parents() {
+        let generations = get_process_id_generations(
+            1,
+            &child_process_id_map(),
+            &config(false),
+            &process_info_map(),
+        );
+        // 2 and 3 are siblings (same generation); 4 is 3's child, one
+        // generation deeper.
+        assert_eq!(generations, vec![vec![2, 3], vec![4]]);
+    }
+
+    #[test]
+    fn get_process_id_generations_skips_zombies_but_still_walks_their_children() {
+        let generations = get_process_id_generations(
+            1,
+            &child_process_id_map(),
+            &config(true),
+            &process_info_map(),
+        );
+        // 3 is a zombie and dropped from its generation, but 4 (3's child)
+        // still shows up one generation later.
+        assert_eq!(generations, vec![vec![2], vec![4]]);
+    }
 }
@@ -0,0 +1,213 @@
+use std::{collections::HashMap, num::TryFromIntError, time::Duration};
+
+use thiserror::Error;
+
+/// Operating system process id.
+pub type ProcessId = u32;
+pub(crate) type ProcessIds = Vec<ProcessId>;
+pub(crate) type ChildProcessIdMap = HashMap<ProcessId, ProcessIds>;
+pub(crate) type ChildProcessIdMapFilter = fn(&ProcessInfo) -> bool;
+pub(crate) type ProcessInfoMap = HashMap<ProcessId, ProcessInfo>;
+pub type ProcessInfos = Vec<ProcessInfo>;
+pub type Outputs = Vec<Output>;
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug, Clone)]
+pub struct ProcessInfo {
+    pub process_id: ProcessId,
+    pub parent_process_id: ProcessId,
+    pub name: String,
+    /// Platform-specific process start time: clock ticks since boot on
+    /// Linux (`/proc/[pid]/stat` field 22), whole seconds since the Unix
+    /// epoch on macOS (`pbi_start_tvsec`), and 100ns `FILETIME` units since
+    /// the Windows epoch (`GetProcessTimes` creation time) on Windows. Never
+    /// comparable across platforms or across a reboot; only meaningful as
+    /// a same-snapshot-vs-same-machine re-verification of "is this still
+    /// the same process that occupied this pid".
+    pub start_time: u64,
+    pub status: ProcessStatus,
+    pub uid: u32,
+    pub gid: u32,
+    /// Full command line, when the platform exposes it without extra
+    /// privileges. `None` rather than an empty string when it could not
+    /// be read (e.g. access denied, or the process already exited).
+    pub cmdline: Option<String>,
+}
+
+/// Coarse process state, modelled after the POSIX `ps` state letters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcessStatus {
+    Run,
+    Sleep,
+    Idle,
+    Stop,
+    Zombie,
+    Dead,
+    Unknown,
+}
+
+/// Signal to deliver to a process.
+///
+/// On Unix this maps directly onto the `libc` signal constants. On
+/// Windows only [`Signal::Sigkill`] has a native equivalent
+/// (`TerminateProcess`); the others are delivered as a best-effort
+/// graceful-shutdown request (`GenerateConsoleCtrlEvent` / `WM_CLOSE`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Signal {
+    Sigterm,
+    Sigint,
+    Sighup,
+    Sigkill,
+}
+
+impl Default for Signal {
+    fn default() -> Self {
+        Self::Sigkill
+    }
+}
+
+/// Options controlling how [`crate::kill_tree`] walks and kills a process tree.
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub include_target: bool,
+    /// Signal delivered to every process in the tree.
+    pub signal: Signal,
+    /// When set, `signal` is sent first and survivors are given up to this
+    /// long to exit on their own before [`Signal::Sigkill`] is sent to them.
+    pub escalation_timeout: Option<Duration>,
+    /// On Linux, block until every killed process has actually exited (its
+    /// pidfd becomes readable) instead of returning as soon as the signal
+    /// is delivered. Produces [`Output::Reaped`] entries. The exit status is
+    /// only collected when the process is our own child; other processes
+    /// are left for their parent (or init) to reap.
+    /// Ignored on platforms without pidfd support.
+    pub wait_for_exit: bool,
+    /// Skip processes already in [`ProcessStatus::Zombie`] or
+    /// [`ProcessStatus::Dead`] state instead of signalling them. Zombies
+    /// are already terminated and just awaiting reaping by their parent,
+    /// so signalling them is always a wasted syscall.
+    pub skip_zombies: bool,
+    /// On Unix, in addition to the individually enumerated process ids,
+    /// also signal each target's process group (`killpg`/`kill(-pgid,
+    /// sig)`). Catches grandchildren that double-forked and reparented to
+    /// init between the snapshot and the kill, so their parent link no
+    /// longer points into the tree. Ignored on Windows, which has no
+    /// process-group equivalent.
+    ///
+    /// Process groups reached only this way are not covered by
+    /// `escalation_timeout`: they get a single signal with no follow-up
+    /// [`Signal::Sigkill`], unlike the individually enumerated process ids.
+    pub use_process_groups: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            include_target: true,
+            signal: Signal::default(),
+            escalation_timeout: None,
+            wait_for_exit: false,
+            skip_zombies: false,
+            use_process_groups: false,
+        }
+    }
+}
+
+pub(crate) enum KillOutput {
+    Killed {
+        process_id: ProcessId,
+    },
+    /// The process was signalled and confirmed to have exited (pidfd-based
+    /// killers with `Config::wait_for_exit` only).
+    Reaped {
+        process_id: ProcessId,
+    },
+    MaybeAlreadyTerminated {
+        process_id: ProcessId,
+        source: Error,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub enum Output {
+    Killed {
+        process_id: ProcessId,
+        parent_process_id: ProcessId,
+        name: String,
+        status: ProcessStatus,
+        uid: u32,
+        gid: u32,
+        cmdline: Option<String>,
+    },
+    /// The process was signalled and confirmed to have exited (pidfd-based
+    /// killers with `Config::wait_for_exit` only).
+    Reaped {
+        process_id: ProcessId,
+    },
+    MaybeAlreadyTerminated {
+        process_id: ProcessId,
+        source: Error,
+    },
+}
+
+pub(crate) trait Killable {
+    /// Kill `process_id`, first re-verifying that it is still the same
+    /// process captured in the snapshot. When `expected_start_time` is
+    /// `Some` and no longer matches the live process' start time (the pid
+    /// was recycled into an unrelated process), implementations must
+    /// return `KillOutput::MaybeAlreadyTerminated` instead of signalling it.
+    fn kill(
+        &self,
+        process_id: ProcessId,
+        signal: Signal,
+        expected_start_time: Option<u64>,
+    ) -> Result<KillOutput>;
+}
+
+#[derive(Debug, Error, Clone)]
+pub enum Error {
+    #[error("Io error: {0}")]
+    Io(String),
+    #[error("Invalid process id. process id: {process_id}, reason: {reason}")]
+    InvalidProcessId { process_id: ProcessId, reason: String },
+    #[error("Process id is too large. process id: {process_id}, available max process id: {available_max_process_id}")]
+    ProcessIdTooLarge {
+        process_id: ProcessId,
+        available_max_process_id: ProcessId,
+    },
+    #[error("Invalid cast. reason: {reason}")]
+    InvalidCast {
+        source: TryFromIntError,
+        reason: String,
+    },
+    #[error("Process id {process_id} was recycled into a different process since the snapshot was taken")]
+    ProcessIdRecycled { process_id: ProcessId },
+    #[cfg(windows)]
+    #[error("Windows error: {0}")]
+    Windows(windows::core::Error),
+    #[cfg(feature = "tokio")]
+    #[error("Join error: {0}")]
+    Join(String),
+}
+
+impl From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Self {
+        Self::Io(error.to_string())
+    }
+}
+
+impl From<TryFromIntError> for Error {
+    fn from(error: TryFromIntError) -> Self {
+        Self::InvalidCast {
+            source: error,
+            reason: "integer conversion failed".into(),
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl From<tokio::task::JoinError> for Error {
+    fn from(error: tokio::task::JoinError) -> Self {
+        Self::Join(error.to_string())
+    }
+}
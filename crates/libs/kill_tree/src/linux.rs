@@ -0,0 +1,481 @@
+use crate::core::{
+    Config, Error, Killable, KillOutput, ProcessId, ProcessInfo, ProcessInfos, ProcessStatus,
+    Result, Signal,
+};
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    fs,
+    os::fd::{FromRawFd, OwnedFd, RawFd},
+    sync::atomic::{AtomicBool, Ordering},
+};
+use tracing::{debug, instrument};
+
+/// Largest pid Linux can hand out on 64-bit kernels: `PID_MAX_LIMIT`
+/// (2^22), the highest value `/proc/sys/kernel/pid_max` accepts, minus one.
+const AVAILABLE_MAX_PROCESS_ID: u32 = 4_194_304 - 1;
+
+// `libc` does not bind these yet on every supported target, so the raw
+// syscall numbers are used directly, as documented in `syscalls(2)`.
+const SYS_PIDFD_OPEN: libc::c_long = 434;
+const SYS_PIDFD_SEND_SIGNAL: libc::c_long = 424;
+
+pub(crate) fn child_process_id_map_filter(process_info: &ProcessInfo) -> bool {
+    // this process is the kernel's init (pid 1, or self-parented)
+    process_info.parent_process_id == process_info.process_id
+}
+
+pub(crate) fn is_alive(process_id: ProcessId) -> bool {
+    crate::unix::is_alive(process_id)
+}
+
+/// Parse `pid`, `comm` (process name), `state`, `ppid`, and `starttime`
+/// (field 22) out of `/proc/[pid]/stat`.
+///
+/// The `comm` field is parenthesized and may itself contain spaces or
+/// parentheses, so it is extracted between the first `(` and the last `)`
+/// rather than by naive whitespace splitting. Every field after it is then
+/// addressed by its offset from that closing paren: field 3 (state) is the
+/// first of `rest`, field 4 (ppid) the second, ... field 22 (starttime, in
+/// clock ticks since boot) the twentieth.
+fn parse_stat(stat: &str) -> Option<(ProcessId, String, char, ProcessId, u64)> {
+    let name_start = stat.find('(')?;
+    let name_end = stat.rfind(')')?;
+    let name = stat.get(name_start + 1..name_end)?.to_string();
+    let pid: ProcessId = stat.get(..name_start)?.trim().parse().ok()?;
+    let rest = stat.get(name_end + 1..)?;
+    let fields: Vec<&str> = rest.split_whitespace().collect();
+    let state: char = fields.first()?.chars().next()?;
+    let parent_process_id: ProcessId = fields.get(1)?.parse().ok()?;
+    // fields[0] is field 3 (state), so field 22 is fields[22 - 3] = fields[19]
+    let start_time: u64 = fields.get(19)?.parse().ok()?;
+    Some((pid, name, state, parent_process_id, start_time))
+}
+
+/// Map a `/proc/[pid]/stat` state letter to [`ProcessStatus`].
+fn parse_status(state: char) -> ProcessStatus {
+    match state {
+        'R' => ProcessStatus::Run,
+        'S' | 'D' => ProcessStatus::Sleep,
+        'I' => ProcessStatus::Idle,
+        'T' | 't' => ProcessStatus::Stop,
+        'Z' => ProcessStatus::Zombie,
+        'X' | 'x' => ProcessStatus::Dead,
+        _ => ProcessStatus::Unknown,
+    }
+}
+
+/// Parse the real uid/gid out of `/proc/[pid]/status`'s `Uid:`/`Gid:` lines,
+/// each of which lists the real, effective, saved, and filesystem ids in
+/// that order; only the first (real) one is used.
+fn get_uid_gid(process_id: ProcessId) -> (u32, u32) {
+    let Ok(status) = fs::read_to_string(format!("/proc/{process_id}/status")) else {
+        return (0, 0);
+    };
+    let first_field = |prefix: &str| {
+        status
+            .lines()
+            .find_map(|line| line.strip_prefix(prefix))
+            .and_then(|rest| rest.split_whitespace().next())
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(0)
+    };
+    (first_field("Uid:"), first_field("Gid:"))
+}
+
+/// Read `/proc/[pid]/cmdline`'s NUL-separated arguments, joined with spaces.
+///
+/// Returns `None` when the process has no command line to report (e.g. a
+/// kernel thread) rather than an empty string.
+fn get_cmdline(process_id: ProcessId) -> Option<String> {
+    let bytes = fs::read(format!("/proc/{process_id}/cmdline")).ok()?;
+    let cmdline = bytes
+        .split(|&byte| byte == 0)
+        .filter(|arg| !arg.is_empty())
+        .map(|arg| String::from_utf8_lossy(arg).into_owned())
+        .collect::<Vec<_>>()
+        .join(" ");
+    if cmdline.is_empty() {
+        None
+    } else {
+        Some(cmdline)
+    }
+}
+
+#[instrument]
+pub(crate) fn get_process_info(process_id: ProcessId) -> Option<ProcessInfo> {
+    let stat = fs::read_to_string(format!("/proc/{process_id}/stat")).ok()?;
+    let (pid, name, state, parent_process_id, start_time) = parse_stat(&stat)?;
+    let (uid, gid) = get_uid_gid(pid);
+    Some(ProcessInfo {
+        process_id: pid,
+        parent_process_id,
+        name,
+        start_time,
+        status: parse_status(state),
+        uid,
+        gid,
+        cmdline: get_cmdline(pid),
+    })
+}
+
+/// Read the target's start time straight from `/proc`, for re-verifying a
+/// snapshot's [`ProcessInfo::start_time`] just before signalling it.
+fn get_start_time(process_id: ProcessId) -> Option<u64> {
+    let stat = fs::read_to_string(format!("/proc/{process_id}/stat")).ok()?;
+    let (_, _, _, _, start_time) = parse_stat(&stat)?;
+    Some(start_time)
+}
+
+/// Read `pgrp` (field 5) out of `/proc/[pid]/stat`, for
+/// `Config::use_process_groups`.
+pub(crate) fn get_process_group_id(process_id: ProcessId) -> Option<ProcessId> {
+    let stat = fs::read_to_string(format!("/proc/{process_id}/stat")).ok()?;
+    let name_end = stat.rfind(')')?;
+    let rest = stat.get(name_end + 1..)?;
+    // fields[0] is field 3 (state), so field 5 (pgrp) is fields[5 - 3] = fields[2]
+    rest.split_whitespace().nth(2)?.parse().ok()
+}
+
+#[instrument]
+pub(crate) fn get_process_infos() -> Result<ProcessInfos> {
+    let mut process_infos = ProcessInfos::new();
+    for entry in fs::read_dir("/proc")? {
+        let entry = entry?;
+        let Some(process_id) = entry.file_name().to_str().and_then(|s| s.parse().ok()) else {
+            continue;
+        };
+        let Some(process_info) = get_process_info(process_id) else {
+            debug!(process_id, "failed to get process info");
+            continue;
+        };
+        process_infos.push(process_info);
+    }
+    Ok(process_infos)
+}
+
+fn pidfd_open(process_id: ProcessId) -> Option<OwnedFd> {
+    let result = unsafe { libc::syscall(SYS_PIDFD_OPEN, process_id as libc::pid_t, 0_u32) };
+    if result < 0 {
+        return None;
+    }
+    Some(unsafe { OwnedFd::from_raw_fd(result as RawFd) })
+}
+
+fn pidfd_send_signal(pidfd: &OwnedFd, signal: Signal) -> std::io::Result<()> {
+    use std::os::fd::AsRawFd;
+    let result = unsafe {
+        libc::syscall(
+            SYS_PIDFD_SEND_SIGNAL,
+            pidfd.as_raw_fd(),
+            crate::unix::to_libc_signal(signal),
+            std::ptr::null::<libc::siginfo_t>(),
+            0_u32,
+        )
+    };
+    if result == 0 {
+        Ok(())
+    } else {
+        Err(std::io::Error::last_os_error())
+    }
+}
+
+/// Block until `pidfd` becomes readable, i.e. the process it refers to has
+/// exited. Only reaps the zombie (via `waitid(P_PIDFD, ...)`) when the
+/// process is our own child; for arbitrary targets the kernel still signals
+/// readiness, it is just left for the owning parent (or init) to reap.
+fn wait_for_exit(pidfd: &OwnedFd) -> std::io::Result<()> {
+    use std::os::fd::AsRawFd;
+    let mut poll_fd = libc::pollfd {
+        fd: pidfd.as_raw_fd(),
+        events: libc::POLLIN,
+        revents: 0,
+    };
+    loop {
+        let result = unsafe { libc::poll(&mut poll_fd, 1, -1) };
+        if result < 0 {
+            let error = std::io::Error::last_os_error();
+            if error.kind() == std::io::ErrorKind::Interrupted {
+                continue;
+            }
+            return Err(error);
+        }
+        break;
+    }
+    reap(pidfd)
+}
+
+// `libc` does not bind `P_PIDFD` on every target we support, so the raw
+// idtype value is passed directly, as documented in `waitid(2)`.
+const P_PIDFD: libc::c_int = 3;
+
+/// Collect the exit status of the process `pidfd` refers to via
+/// `waitid(P_PIDFD, ...)`, so it doesn't linger as a zombie.
+///
+/// Only succeeds when the process is our own child; for an arbitrary
+/// target, `ECHILD` is expected (the kernel already signalled exit via
+/// `poll`, reaping is just left to its actual parent or init) and is not
+/// treated as an error.
+fn reap(pidfd: &OwnedFd) -> std::io::Result<()> {
+    use std::os::fd::AsRawFd;
+    let mut siginfo: libc::siginfo_t = unsafe { std::mem::zeroed() };
+    let result = unsafe {
+        libc::waitid(P_PIDFD, pidfd.as_raw_fd() as libc::id_t, &mut siginfo, libc::WEXITED)
+    };
+    if result == 0 {
+        return Ok(());
+    }
+    let error = std::io::Error::last_os_error();
+    if error.raw_os_error() == Some(libc::ECHILD) {
+        return Ok(());
+    }
+    Err(error)
+}
+
+/// `pidfd_open` is only available on kernels >= 5.3; older kernels return
+/// `ENOSYS`. Cached after the first call so every subsequent kill on this
+/// process skips straight to the `kill(2)` fallback instead of re-probing.
+static PIDFD_UNSUPPORTED: AtomicBool = AtomicBool::new(false);
+
+struct Killer {
+    pidfds: RefCell<HashMap<ProcessId, OwnedFd>>,
+    wait_for_exit: bool,
+}
+
+impl Killable for Killer {
+    fn kill(
+        &self,
+        process_id: ProcessId,
+        signal: Signal,
+        expected_start_time: Option<u64>,
+    ) -> Result<KillOutput> {
+        if let Some(expected_start_time) = expected_start_time {
+            if get_start_time(process_id) != Some(expected_start_time) {
+                debug!(
+                    process_id,
+                    "Process id was recycled since the snapshot was taken"
+                );
+                return Ok(KillOutput::MaybeAlreadyTerminated {
+                    process_id,
+                    source: Error::ProcessIdRecycled { process_id },
+                });
+            }
+        }
+
+        // Borrowed rather than removed: `kill_with_escalation` may call
+        // this a second time (with `Signal::Sigkill`) for a survivor of
+        // the first round, and that retry must still target the exact
+        // same process the pidfd refers to, not fall back to the racier
+        // pid-based path.
+        let pidfds = self.pidfds.borrow();
+        let Some(pidfd) = pidfds.get(&process_id) else {
+            // No pidfd (either pidfd_open is unsupported on this kernel, or
+            // the process was gone by the time we tried to open one).
+            // Fall back to signalling by pid.
+            drop(pidfds);
+            return crate::unix::kill(process_id, signal);
+        };
+
+        let send_result = pidfd_send_signal(pidfd, signal);
+        let kill_output = match send_result {
+            Ok(()) => KillOutput::Killed { process_id },
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+                // ESRCH: the process already exited.
+                KillOutput::MaybeAlreadyTerminated {
+                    process_id,
+                    source: error.into(),
+                }
+            }
+            Err(error) => return Err(error.into()),
+        };
+
+        if matches!(kill_output, KillOutput::Killed { .. }) && self.wait_for_exit {
+            wait_for_exit(pidfd)?;
+            return Ok(KillOutput::Reaped { process_id });
+        }
+        Ok(kill_output)
+    }
+}
+
+/// Open a pidfd for every process in `process_ids` up front (snapshot
+/// time), so a kill can be retried against the exact same process a
+/// second time (escalation) without the TOCTOU window of re-resolving
+/// the pid later. Shared by both the blocking and tokio backends'
+/// `new_killer`.
+fn open_pidfds(process_ids: &[ProcessId]) -> HashMap<ProcessId, OwnedFd> {
+    let mut pidfds = HashMap::new();
+    if PIDFD_UNSUPPORTED.load(Ordering::Relaxed) {
+        return pidfds;
+    }
+    for &process_id in process_ids {
+        match pidfd_open(process_id) {
+            Some(pidfd) => {
+                pidfds.insert(process_id, pidfd);
+            }
+            None => {
+                let error = std::io::Error::last_os_error();
+                if error.raw_os_error() == Some(libc::ENOSYS) {
+                    debug!("pidfd_open is not supported on this kernel, falling back to kill(2)");
+                    PIDFD_UNSUPPORTED.store(true, Ordering::Relaxed);
+                    pidfds.clear();
+                    break;
+                }
+                // process may already be gone; let `kill` below fall
+                // back to the pid path, which will report it as
+                // already terminated.
+            }
+        }
+    }
+    pidfds
+}
+
+/// One-shot pidfd kill shared by the concurrent tokio backend's tasks:
+/// looks up (rather than removes) the pidfd that was opened for
+/// `process_id` at snapshot time, so a later escalation retry for the
+/// same process still finds it.
+fn kill_one(
+    process_id: ProcessId,
+    signal: Signal,
+    expected_start_time: Option<u64>,
+    should_wait_for_exit: bool,
+    pidfds: &std::sync::Mutex<HashMap<ProcessId, OwnedFd>>,
+) -> Result<KillOutput> {
+    if let Some(expected_start_time) = expected_start_time {
+        if get_start_time(process_id) != Some(expected_start_time) {
+            debug!(
+                process_id,
+                "Process id was recycled since the snapshot was taken"
+            );
+            return Ok(KillOutput::MaybeAlreadyTerminated {
+                process_id,
+                source: Error::ProcessIdRecycled { process_id },
+            });
+        }
+    }
+
+    let pidfds = pidfds.lock().unwrap();
+    let Some(pidfd) = pidfds.get(&process_id) else {
+        drop(pidfds);
+        return crate::unix::kill(process_id, signal);
+    };
+
+    let send_result = pidfd_send_signal(pidfd, signal);
+    let kill_output = match send_result {
+        Ok(()) => KillOutput::Killed { process_id },
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+            KillOutput::MaybeAlreadyTerminated {
+                process_id,
+                source: error.into(),
+            }
+        }
+        Err(error) => return Err(error.into()),
+    };
+
+    if matches!(kill_output, KillOutput::Killed { .. }) && should_wait_for_exit {
+        wait_for_exit(pidfd)?;
+        return Ok(KillOutput::Reaped { process_id });
+    }
+    Ok(kill_output)
+}
+
+#[cfg(feature = "tokio")]
+pub(crate) mod tokio {
+    use super::*;
+    use async_trait::async_trait;
+    use std::sync::{Arc, Mutex};
+
+    pub(crate) use super::child_process_id_map_filter;
+    pub(crate) use super::is_alive;
+
+    struct Killer {
+        // Shared (not per-task) so every concurrent kill task, including an
+        // escalation retry for the same process, targets the exact pidfd
+        // opened for it at snapshot time instead of reopening one later.
+        pidfds: Arc<Mutex<HashMap<ProcessId, OwnedFd>>>,
+        wait_for_exit: bool,
+    }
+
+    #[async_trait]
+    impl crate::tokio::Killable for Killer {
+        async fn kill(
+            &self,
+            process_id: ProcessId,
+            signal: Signal,
+            expected_start_time: Option<u64>,
+        ) -> Result<KillOutput> {
+            let pidfds = Arc::clone(&self.pidfds);
+            let wait_for_exit = self.wait_for_exit;
+            ::tokio::task::spawn_blocking(move || {
+                kill_one(process_id, signal, expected_start_time, wait_for_exit, &pidfds)
+            })
+            .await?
+        }
+    }
+
+    pub(crate) fn new_killer(
+        config: &Config,
+        process_ids: &[ProcessId],
+    ) -> Result<impl crate::tokio::Killable> {
+        Ok(Killer {
+            pidfds: Arc::new(Mutex::new(super::open_pidfds(process_ids))),
+            wait_for_exit: config.wait_for_exit,
+        })
+    }
+}
+
+pub(crate) fn new_killer(config: &Config, process_ids: &[ProcessId]) -> Result<impl Killable> {
+    Ok(Killer {
+        pidfds: RefCell::new(open_pidfds(process_ids)),
+        wait_for_exit: config.wait_for_exit,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_stat_plain_comm() {
+        let stat = "1234 (bash) S 1000 1234 1234 0 -1 4194560 100 0 0 0 0 0 0 0 20 0 1 0 56789 0 0";
+        let (pid, name, state, parent_process_id, start_time) = parse_stat(stat).unwrap();
+        assert_eq!(pid, 1234);
+        assert_eq!(name, "bash");
+        assert_eq!(state, 'S');
+        assert_eq!(parent_process_id, 1000);
+        assert_eq!(start_time, 56789);
+    }
+
+    #[test]
+    fn parse_stat_comm_with_spaces_and_parens() {
+        // `comm` can itself contain spaces and parentheses (e.g. a renamed
+        // process), so it must be found via the first `(` / last `)`.
+        let stat =
+            "4321 (my (weird) proc) R 1 4321 4321 0 -1 4194304 0 0 0 0 0 0 0 0 20 0 1 0 99 0 0";
+        let (pid, name, state, parent_process_id, start_time) = parse_stat(stat).unwrap();
+        assert_eq!(pid, 4321);
+        assert_eq!(name, "my (weird) proc");
+        assert_eq!(state, 'R');
+        assert_eq!(parent_process_id, 1);
+        assert_eq!(start_time, 99);
+    }
+
+    #[test]
+    fn parse_stat_rejects_truncated_input() {
+        assert!(parse_stat("1234 (bash) S 1000").is_none());
+        assert!(parse_stat("not a stat line").is_none());
+    }
+
+    #[test]
+    fn parse_status_maps_every_known_state() {
+        assert_eq!(parse_status('R'), ProcessStatus::Run);
+        assert_eq!(parse_status('S'), ProcessStatus::Sleep);
+        assert_eq!(parse_status('D'), ProcessStatus::Sleep);
+        assert_eq!(parse_status('I'), ProcessStatus::Idle);
+        assert_eq!(parse_status('T'), ProcessStatus::Stop);
+        assert_eq!(parse_status('t'), ProcessStatus::Stop);
+        assert_eq!(parse_status('Z'), ProcessStatus::Zombie);
+        assert_eq!(parse_status('X'), ProcessStatus::Dead);
+        assert_eq!(parse_status('x'), ProcessStatus::Dead);
+        assert_eq!(parse_status('?'), ProcessStatus::Unknown);
+    }
+}
@@ -1,5 +1,6 @@
 use crate::{
     common::{self, Impl, ProcessInfo, ProcessInfos},
+    core::{Config, Killable, KillOutput, ProcessStatus, Result},
     ProcessId,
 };
 use std::{
@@ -11,8 +12,88 @@ use tracing::{debug, instrument};
 
 const AVAILABLE_MAX_PROCESS_ID: u32 = 99999 - 1;
 
-#[instrument]
-pub(crate) async fn get_process_info(process_id: ProcessId) -> Option<ProcessInfo> {
+pub(crate) fn child_process_id_map_filter(process_info: &ProcessInfo) -> bool {
+    // this process is the kernel's launchd / init (pid 1, or self-parented)
+    process_info.parent_process_id == process_info.process_id
+}
+
+pub(crate) fn is_alive(process_id: ProcessId) -> bool {
+    crate::unix::is_alive(process_id)
+}
+
+struct Killer {}
+
+impl Killable for Killer {
+    fn kill(
+        &self,
+        process_id: ProcessId,
+        signal: crate::core::Signal,
+        expected_start_time: Option<u64>,
+    ) -> Result<KillOutput> {
+        if let Some(expected_start_time) = expected_start_time {
+            if get_start_time(process_id) != Some(expected_start_time) {
+                debug!(process_id, "Process id was recycled since the snapshot was taken");
+                return Ok(KillOutput::MaybeAlreadyTerminated {
+                    process_id,
+                    source: crate::core::Error::ProcessIdRecycled { process_id },
+                });
+            }
+        }
+        crate::unix::kill(process_id, signal)
+    }
+}
+
+pub(crate) fn new_killer(_config: &Config, _process_ids: &[ProcessId]) -> Result<impl Killable> {
+    Ok(Killer {})
+}
+
+#[cfg(feature = "tokio")]
+pub(crate) mod tokio {
+    use super::*;
+    use async_trait::async_trait;
+
+    pub(crate) use super::child_process_id_map_filter;
+    pub(crate) use super::is_alive;
+
+    #[derive(Clone, Copy)]
+    struct Killer {}
+
+    #[async_trait]
+    impl crate::tokio::Killable for Killer {
+        async fn kill(
+            &self,
+            process_id: ProcessId,
+            signal: crate::core::Signal,
+            expected_start_time: Option<u64>,
+        ) -> Result<KillOutput> {
+            ::tokio::task::spawn_blocking(move || {
+                if let Some(expected_start_time) = expected_start_time {
+                    if super::get_start_time(process_id) != Some(expected_start_time) {
+                        debug!(
+                            process_id,
+                            "Process id was recycled since the snapshot was taken"
+                        );
+                        return Ok(KillOutput::MaybeAlreadyTerminated {
+                            process_id,
+                            source: crate::core::Error::ProcessIdRecycled { process_id },
+                        });
+                    }
+                }
+                crate::unix::kill(process_id, signal)
+            })
+            .await?
+        }
+    }
+
+    pub(crate) fn new_killer(
+        _config: &Config,
+        _process_ids: &[ProcessId],
+    ) -> Result<impl crate::tokio::Killable> {
+        Ok(Killer {})
+    }
+}
+
+fn get_proc_bsdinfo(process_id: ProcessId) -> Option<libproc::proc_bsdinfo> {
     let proc_bsdinfo_size = match u32::try_from(std::mem::size_of::<libproc::proc_bsdinfo>()) {
         Ok(x) => x,
         Err(e) => {
@@ -56,6 +137,92 @@ pub(crate) async fn get_process_info(process_id: ProcessId) -> Option<ProcessInf
         debug!(error = ?error, process_id, "failed to get process info");
         return None;
     }
+    Some(proc_bsdinfo)
+}
+
+/// Read the target's start time straight from the kernel, for re-verifying
+/// a snapshot's [`ProcessInfo::start_time`] just before signalling it.
+fn get_start_time(process_id: ProcessId) -> Option<u64> {
+    get_proc_bsdinfo(process_id).map(|proc_bsdinfo| proc_bsdinfo.pbi_start_tvsec)
+}
+
+/// Read the target's process group id, for `Config::use_process_groups`.
+pub(crate) fn get_process_group_id(process_id: ProcessId) -> Option<ProcessId> {
+    get_proc_bsdinfo(process_id).map(|proc_bsdinfo| proc_bsdinfo.pbi_pgid)
+}
+
+/// Map `proc_bsdinfo.pbi_status` (one of the `S*` constants in
+/// `sys/proc.h`, e.g. `SRUN`, `SZOMB`) to [`ProcessStatus`].
+fn parse_status(pbi_status: u32) -> ProcessStatus {
+    match pbi_status {
+        1 => ProcessStatus::Idle,
+        2 => ProcessStatus::Run,
+        3 => ProcessStatus::Sleep,
+        4 => ProcessStatus::Stop,
+        5 => ProcessStatus::Zombie,
+        _ => ProcessStatus::Unknown,
+    }
+}
+
+/// Best-effort full command line via `sysctl(CTL_KERN, KERN_PROCARGS2, pid)`.
+///
+/// The returned buffer is an `argc: i32` followed by the NUL-terminated
+/// executable path (then NUL padding up to a word boundary), then `argc`
+/// more NUL-terminated strings which are the actual `argv`. Returns `None`
+/// when the target is owned by another user, already exited, or the buffer
+/// is shaped unexpectedly.
+fn get_cmdline(process_id: ProcessId) -> Option<String> {
+    let process_id_sign = i32::try_from(process_id).ok()?;
+    let mut mib = [libc::CTL_KERN, libc::KERN_PROCARGS2, process_id_sign];
+    let mut size = 0_usize;
+    unsafe {
+        if libc::sysctl(
+            mib.as_mut_ptr(),
+            mib.len() as u32,
+            ptr::null_mut(),
+            &mut size,
+            ptr::null_mut(),
+            0,
+        ) != 0
+        {
+            return None;
+        }
+    }
+    let mut buffer = vec![0_u8; size];
+    unsafe {
+        if libc::sysctl(
+            mib.as_mut_ptr(),
+            mib.len() as u32,
+            buffer.as_mut_ptr().cast(),
+            &mut size,
+            ptr::null_mut(),
+            0,
+        ) != 0
+        {
+            return None;
+        }
+    }
+    buffer.truncate(size);
+    let argc = i32::from_ne_bytes(buffer.get(..4)?.try_into().ok()?);
+    if argc <= 0 {
+        return None;
+    }
+    let mut rest = buffer.get(4..)?;
+    // skip the NUL-terminated executable path and its NUL padding
+    rest = rest.get(rest.iter().position(|&byte| byte == 0)?..)?;
+    rest = rest.get(rest.iter().position(|&byte| byte != 0)?..)?;
+    let mut args = Vec::with_capacity(argc as usize);
+    for _ in 0..argc {
+        let arg_end = rest.iter().position(|&byte| byte == 0)?;
+        args.push(String::from_utf8_lossy(&rest[..arg_end]).into_owned());
+        rest = rest.get(arg_end + 1..)?;
+    }
+    Some(args.join(" "))
+}
+
+#[instrument]
+pub(crate) async fn get_process_info(process_id: ProcessId) -> Option<ProcessInfo> {
+    let proc_bsdinfo = get_proc_bsdinfo(process_id)?;
     let name = unsafe { CStr::from_ptr(std::ptr::addr_of!(proc_bsdinfo.pbi_name[0])) }
         .to_string_lossy()
         .to_string();
@@ -63,6 +230,11 @@ pub(crate) async fn get_process_info(process_id: ProcessId) -> Option<ProcessInf
         process_id,
         parent_process_id: proc_bsdinfo.pbi_ppid,
         name,
+        start_time: proc_bsdinfo.pbi_start_tvsec,
+        status: parse_status(proc_bsdinfo.pbi_status),
+        uid: proc_bsdinfo.pbi_uid,
+        gid: proc_bsdinfo.pbi_gid,
+        cmdline: get_cmdline(process_id),
     })
 }
 
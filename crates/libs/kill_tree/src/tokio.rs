@@ -0,0 +1,169 @@
+use crate::core::{
+    Config, Error, KillOutput, Outputs, ProcessId, ProcessIds, ProcessInfoMap, ProcessInfos,
+    Result, Signal,
+};
+use async_trait::async_trait;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::debug;
+
+#[cfg(target_os = "linux")]
+use crate::linux::tokio as imp;
+#[cfg(target_os = "macos")]
+use crate::macos::tokio as imp;
+#[cfg(windows)]
+use crate::windows::tokio as imp;
+
+/// Async counterpart of [`crate::core::Killable`], for the concurrent
+/// tokio backend.
+#[async_trait]
+pub(crate) trait Killable: Send + Sync {
+    async fn kill(
+        &self,
+        process_id: ProcessId,
+        signal: Signal,
+        expected_start_time: Option<u64>,
+    ) -> Result<KillOutput>;
+}
+
+/// One failed kill, collected instead of aborting the rest of the tree.
+#[derive(Debug)]
+pub struct KillError {
+    pub process_id: ProcessId,
+    pub source: Error,
+}
+
+/// Send `signal` to every process in `generations`, deepest generation
+/// first, fanning each generation's kills out onto a
+/// [`::tokio::task::JoinSet`] — the same pattern
+/// [`crate::macos::get_process_infos`] already uses to gather process info
+/// concurrently — only moving on to a shallower generation once every
+/// deeper one has finished. This preserves "children before parents" while
+/// letting unrelated branches of the tree progress in parallel. Returns the
+/// real [`KillOutput`] for every process actually signalled; a failed kill
+/// is recorded in `errors` instead of aborting the rest of the tree.
+async fn kill_generations(
+    killer: &Arc<impl Killable>,
+    generations: &[ProcessIds],
+    signal: Signal,
+    process_info_map: &ProcessInfoMap,
+    errors: &mut Vec<KillError>,
+) -> std::collections::HashMap<ProcessId, KillOutput> {
+    let mut results = std::collections::HashMap::new();
+    for generation in generations.iter().rev() {
+        let mut tasks: ::tokio::task::JoinSet<(ProcessId, Result<KillOutput>)> =
+            ::tokio::task::JoinSet::new();
+        for &process_id in generation {
+            let killer = Arc::clone(killer);
+            let expected_start_time = process_info_map.get(&process_id).map(|info| info.start_time);
+            tasks.spawn(async move {
+                let result = killer.kill(process_id, signal, expected_start_time).await;
+                (process_id, result)
+            });
+        }
+        while let Some(joined) = tasks.join_next().await {
+            let (process_id, result) = match joined {
+                Ok(x) => x,
+                Err(error) => {
+                    debug!(error = ?error, "kill task panicked or was cancelled");
+                    continue;
+                }
+            };
+            match result {
+                Ok(kill_output) => {
+                    results.insert(process_id, kill_output);
+                }
+                Err(source) => errors.push(KillError { process_id, source }),
+            }
+        }
+    }
+    results
+}
+
+/// Concurrently kill every process in the tree rooted at `process_id`.
+///
+/// Mirrors [`crate::common::kill_tree_internal`]'s handling of
+/// `Config::use_process_groups` and `Config::escalation_timeout`, just with
+/// each generation's kills run concurrently instead of one pid at a time.
+pub(crate) async fn kill_tree_internal(
+    process_id: ProcessId,
+    config: &Config,
+    process_infos: ProcessInfos,
+) -> Result<(Outputs, Vec<KillError>)> {
+    let child_process_id_map =
+        crate::common::get_child_process_id_map(&process_infos, imp::child_process_id_map_filter);
+    let mut process_info_map = crate::common::get_process_info_map(process_infos);
+    let generations = crate::common::get_process_id_generations(
+        process_id,
+        &child_process_id_map,
+        config,
+        &process_info_map,
+    );
+    let all_process_ids: ProcessIds = generations.iter().flatten().copied().collect();
+    let killer = Arc::new(imp::new_killer(config, &all_process_ids)?);
+
+    if config.use_process_groups {
+        crate::common::kill_process_groups(&all_process_ids, config.signal);
+    }
+
+    let mut errors = Vec::new();
+    let mut kill_outputs =
+        kill_generations(&killer, &generations, config.signal, &process_info_map, &mut errors)
+            .await;
+
+    if config.signal != Signal::Sigkill {
+        if let Some(escalation_timeout) = config.escalation_timeout {
+            let deadline = Instant::now() + escalation_timeout;
+            let mut survivors: std::collections::HashSet<ProcessId> = kill_outputs
+                .iter()
+                .filter(|(_, kill_output)| matches!(kill_output, KillOutput::Killed { .. }))
+                .map(|(&process_id, _)| process_id)
+                .collect();
+            while !survivors.is_empty() && Instant::now() < deadline {
+                survivors.retain(|&process_id| imp::is_alive(process_id));
+                if survivors.is_empty() {
+                    break;
+                }
+                ::tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+
+            if !survivors.is_empty() {
+                let survivor_generations: Vec<ProcessIds> = generations
+                    .iter()
+                    .map(|generation| {
+                        generation
+                            .iter()
+                            .copied()
+                            .filter(|process_id| survivors.contains(process_id))
+                            .collect()
+                    })
+                    .collect();
+                let escalated = kill_generations(
+                    &killer,
+                    &survivor_generations,
+                    Signal::Sigkill,
+                    &process_info_map,
+                    &mut errors,
+                )
+                .await;
+                kill_outputs.extend(escalated);
+            }
+        }
+    }
+
+    let mut outputs = Outputs::new();
+    // kill children first
+    for generation in generations.into_iter().rev() {
+        for process_id in generation {
+            let Some(kill_output) = kill_outputs.remove(&process_id) else {
+                continue;
+            };
+            if let Some(output) = crate::common::parse_kill_output(kill_output, &mut process_info_map)
+            {
+                outputs.push(output);
+            }
+        }
+    }
+
+    Ok((outputs, errors))
+}
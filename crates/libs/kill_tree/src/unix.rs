@@ -0,0 +1,77 @@
+use crate::core::{Error, KillOutput, ProcessId, Result, Signal};
+
+pub(crate) fn validate_process_id(
+    process_id: ProcessId,
+    available_max_process_id: ProcessId,
+) -> Result<()> {
+    if process_id > available_max_process_id {
+        return Err(Error::ProcessIdTooLarge {
+            process_id,
+            available_max_process_id,
+        });
+    }
+    Ok(())
+}
+
+pub(crate) fn to_libc_signal(signal: Signal) -> libc::c_int {
+    match signal {
+        Signal::Sigterm => libc::SIGTERM,
+        Signal::Sigint => libc::SIGINT,
+        Signal::Sighup => libc::SIGHUP,
+        Signal::Sigkill => libc::SIGKILL,
+    }
+}
+
+/// Send `signal` to `process_id` via `libc::kill`.
+pub(crate) fn kill(process_id: ProcessId, signal: Signal) -> Result<KillOutput> {
+    let process_id_sign = process_id as libc::pid_t;
+    let result = unsafe { libc::kill(process_id_sign, to_libc_signal(signal)) };
+    if result == 0 {
+        return Ok(KillOutput::Killed { process_id });
+    }
+    let error = std::io::Error::last_os_error();
+    if error.kind() == std::io::ErrorKind::NotFound {
+        // ESRCH: no such process. This happens when the process is
+        // already terminated. This treat as success.
+        return Ok(KillOutput::MaybeAlreadyTerminated {
+            process_id,
+            source: error.into(),
+        });
+    }
+    Err(error.into())
+}
+
+/// Probe whether `process_id` is still alive, without sending it a signal.
+pub(crate) fn is_alive(process_id: ProcessId) -> bool {
+    let process_id_sign = process_id as libc::pid_t;
+    unsafe { libc::kill(process_id_sign, 0) == 0 }
+}
+
+/// Send `signal` to every process in process group `process_group_id` via
+/// `libc::killpg`.
+pub(crate) fn killpg(process_group_id: ProcessId, signal: Signal) -> Result<()> {
+    let process_group_id_sign = process_group_id as libc::pid_t;
+    let result = unsafe { libc::killpg(process_group_id_sign, to_libc_signal(signal)) };
+    if result == 0 {
+        return Ok(());
+    }
+    let error = std::io::Error::last_os_error();
+    if error.kind() == std::io::ErrorKind::NotFound {
+        // ESRCH: the group is already gone. This treat as success.
+        return Ok(());
+    }
+    Err(error.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_libc_signal_maps_every_variant() {
+        assert_eq!(to_libc_signal(Signal::Sigterm), libc::SIGTERM);
+        assert_eq!(to_libc_signal(Signal::Sigint), libc::SIGINT);
+        assert_eq!(to_libc_signal(Signal::Sighup), libc::SIGHUP);
+        assert_eq!(to_libc_signal(Signal::Sigkill), libc::SIGKILL);
+    }
+}
@@ -1,15 +1,31 @@
-use crate::core::{Config, Error, KillOutput, ProcessId, ProcessInfo, ProcessInfos, Result};
+use crate::core::{
+    Config, Error, KillOutput, ProcessId, ProcessInfo, ProcessInfos, ProcessStatus, Result, Signal,
+};
 use std::ffi;
 use tracing::instrument;
 use windows::Win32::{
-    Foundation::{CloseHandle, ERROR_NO_MORE_FILES, E_ACCESSDENIED, E_INVALIDARG},
+    Foundation::{
+        CloseHandle, BOOL, ERROR_NO_MORE_FILES, E_ACCESSDENIED, E_INVALIDARG, FILETIME, HANDLE,
+        HWND, LPARAM, WPARAM,
+    },
+    Security::{
+        GetSidSubAuthority, GetSidSubAuthorityCount, GetTokenInformation, OpenProcessToken,
+        TokenPrimaryGroup, TokenUser, TOKEN_INFORMATION_CLASS, TOKEN_PRIMARY_GROUP, TOKEN_QUERY,
+        TOKEN_USER,
+    },
     System::{
+        Console::{GenerateConsoleCtrlEvent, CTRL_BREAK_EVENT},
         Diagnostics::ToolHelp::{
             CreateToolhelp32Snapshot, Process32First, Process32Next, PROCESSENTRY32,
             TH32CS_SNAPPROCESS,
         },
-        Threading::{OpenProcess, TerminateProcess, PROCESS_TERMINATE},
+        Threading::{
+            GetExitCodeProcess, GetProcessTimes, OpenProcess, TerminateProcess,
+            PROCESS_QUERY_INFORMATION, PROCESS_QUERY_LIMITED_INFORMATION, PROCESS_TERMINATE,
+            STILL_ACTIVE,
+        },
     },
+    UI::WindowsAndMessaging::{EnumWindows, GetWindowThreadProcessId, PostMessageW, WM_CLOSE},
 };
 
 impl From<windows::core::Error> for Error {
@@ -43,6 +59,153 @@ pub(crate) fn child_process_id_map_filter(process_info: &ProcessInfo) -> bool {
     process_info.parent_process_id == process_info.process_id
 }
 
+pub(crate) fn is_alive(process_id: ProcessId) -> bool {
+    unsafe {
+        let Ok(process_handle) = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, process_id)
+        else {
+            return false;
+        };
+        let mut exit_code = 0_u32;
+        let alive = GetExitCodeProcess(process_handle, &mut exit_code).is_ok()
+            && exit_code == STILL_ACTIVE.0 as u32;
+        let _ = CloseHandle(process_handle);
+        alive
+    }
+}
+
+fn filetime_to_ticks(filetime: FILETIME) -> u64 {
+    (u64::from(filetime.dwHighDateTime) << 32) | u64::from(filetime.dwLowDateTime)
+}
+
+/// Read the target's creation time straight from the kernel, for
+/// re-verifying a snapshot's [`ProcessInfo::start_time`] just before
+/// signalling it.
+pub(crate) fn get_start_time(process_id: ProcessId) -> Option<u64> {
+    unsafe {
+        let process_handle =
+            OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, process_id).ok()?;
+        let mut creation_time = FILETIME::default();
+        let mut exit_time = FILETIME::default();
+        let mut kernel_time = FILETIME::default();
+        let mut user_time = FILETIME::default();
+        let result = GetProcessTimes(
+            process_handle,
+            &mut creation_time,
+            &mut exit_time,
+            &mut kernel_time,
+            &mut user_time,
+        );
+        let _ = CloseHandle(process_handle);
+        result.ok()?;
+        Some(filetime_to_ticks(creation_time))
+    }
+}
+
+/// Best-effort status: Windows has no direct equivalent of the Unix
+/// run/sleep/zombie states, so this only distinguishes "still running" from
+/// "exited" (via the same `GetExitCodeProcess` check as [`is_alive`]).
+pub(crate) fn get_status(process_id: ProcessId) -> ProcessStatus {
+    unsafe {
+        let Ok(process_handle) = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, process_id)
+        else {
+            return ProcessStatus::Unknown;
+        };
+        let mut exit_code = 0_u32;
+        let status = if GetExitCodeProcess(process_handle, &mut exit_code).is_ok() {
+            if exit_code == STILL_ACTIVE.0 as u32 {
+                ProcessStatus::Run
+            } else {
+                ProcessStatus::Dead
+            }
+        } else {
+            ProcessStatus::Unknown
+        };
+        let _ = CloseHandle(process_handle);
+        status
+    }
+}
+
+/// The relative identifier (the last sub-authority) of a token SID: unique
+/// within its issuing authority but, unlike a POSIX uid/gid, not guaranteed
+/// unique machine-wide. Used as the closest available analogue.
+fn get_sid_rid(process_handle: HANDLE, info_class: TOKEN_INFORMATION_CLASS) -> Option<u32> {
+    unsafe {
+        let mut token_handle = HANDLE::default();
+        OpenProcessToken(process_handle, TOKEN_QUERY, &mut token_handle).ok()?;
+        let mut size = 0_u32;
+        let _ = GetTokenInformation(token_handle, info_class, None, 0, &mut size);
+        let mut buffer = vec![0_u8; size as usize];
+        let result = GetTokenInformation(
+            token_handle,
+            info_class,
+            Some(buffer.as_mut_ptr().cast()),
+            size,
+            &mut size,
+        );
+        let _ = CloseHandle(token_handle);
+        result.ok()?;
+        let sid = if info_class == TokenUser {
+            (*buffer.as_ptr().cast::<TOKEN_USER>()).User.Sid
+        } else {
+            (*buffer.as_ptr().cast::<TOKEN_PRIMARY_GROUP>()).PrimaryGroup
+        };
+        let sub_authority_count = *GetSidSubAuthorityCount(sid);
+        if sub_authority_count == 0 {
+            return None;
+        }
+        Some(*GetSidSubAuthority(sid, u32::from(sub_authority_count) - 1))
+    }
+}
+
+/// Owner (uid, gid) analogue, derived from a token query on the process'
+/// user and primary group SIDs. See [`get_sid_rid`] for the caveats.
+pub(crate) fn get_owner_ids(process_id: ProcessId) -> (u32, u32) {
+    unsafe {
+        let Ok(process_handle) = OpenProcess(PROCESS_QUERY_INFORMATION, false, process_id) else {
+            return (0, 0);
+        };
+        let ids = (
+            get_sid_rid(process_handle, TokenUser).unwrap_or(0),
+            get_sid_rid(process_handle, TokenPrimaryGroup).unwrap_or(0),
+        );
+        let _ = CloseHandle(process_handle);
+        ids
+    }
+}
+
+/// Full command line of `process_id`.
+///
+/// Reconstructing this on Windows means reading the target's PEB
+/// (`RTL_USER_PROCESS_PARAMETERS`) through undocumented `ntdll` APIs that
+/// are out of scope for this typed binding layer, so this always returns
+/// `None`.
+pub(crate) fn get_cmdline(_process_id: ProcessId) -> Option<String> {
+    None
+}
+
+unsafe extern "system" fn close_window_proc(window_handle: HWND, target_process_id: LPARAM) -> BOOL {
+    let mut owner_process_id = 0_u32;
+    GetWindowThreadProcessId(window_handle, Some(&mut owner_process_id));
+    if owner_process_id == target_process_id.0 as u32 {
+        let _ = PostMessageW(window_handle, WM_CLOSE, WPARAM(0), LPARAM(0));
+    }
+    true.into()
+}
+
+/// Best-effort graceful shutdown request for `SIGTERM`/`SIGINT`.
+///
+/// Windows has no direct equivalent of a Unix signal, so this sends a
+/// `CTRL_BREAK_EVENT` (only delivered if the target shares our console's
+/// process group) and posts `WM_CLOSE` to every top-level window owned by
+/// the process. Neither is guaranteed to terminate the process, which is
+/// why callers are expected to pair this with `Config::escalation_timeout`.
+fn request_graceful_shutdown(process_id: ProcessId) {
+    unsafe {
+        let _ = GenerateConsoleCtrlEvent(CTRL_BREAK_EVENT, process_id);
+        let _ = EnumWindows(Some(close_window_proc), LPARAM(process_id as isize));
+    }
+}
+
 #[cfg(feature = "blocking")]
 pub(crate) mod blocking {
     use super::*;
@@ -50,17 +213,59 @@ pub(crate) mod blocking {
     struct Killer {}
 
     impl crate::blocking::Killable for Killer {
-        fn kill(&self, process_id: ProcessId) -> Result<KillOutput> {
-            crate::windows::blocking::kill(process_id)
+        fn kill(
+            &self,
+            process_id: ProcessId,
+            signal: Signal,
+            expected_start_time: Option<u64>,
+        ) -> Result<KillOutput> {
+            crate::windows::blocking::kill(process_id, signal, expected_start_time)
         }
     }
 
-    pub(crate) fn new_killer(_config: &Config) -> Result<impl crate::blocking::Killable> {
+    pub(crate) fn new_killer(
+        _config: &Config,
+        _process_ids: &[ProcessId],
+    ) -> Result<impl crate::blocking::Killable> {
         Ok(Killer {})
     }
 
     #[instrument]
-    pub(crate) fn kill(process_id: ProcessId) -> Result<KillOutput> {
+    pub(crate) fn kill(
+        process_id: ProcessId,
+        signal: Signal,
+        expected_start_time: Option<u64>,
+    ) -> Result<KillOutput> {
+        if let Some(expected_start_time) = expected_start_time {
+            if super::get_start_time(process_id) != Some(expected_start_time) {
+                return Ok(KillOutput::MaybeAlreadyTerminated {
+                    process_id,
+                    source: Error::ProcessIdRecycled { process_id },
+                });
+            }
+        }
+        if signal != Signal::Sigkill {
+            if !super::is_alive(process_id) {
+                // Nothing to signal: the process is already gone. Without
+                // this check, a dead or never-existent pid would otherwise
+                // be reported as `Killed` below even though nothing was
+                // delivered to it.
+                return Ok(KillOutput::MaybeAlreadyTerminated {
+                    process_id,
+                    source: Error::InvalidProcessId {
+                        process_id,
+                        reason: "process no longer exists".into(),
+                    },
+                });
+            }
+            // Best-effort request: `CTRL_BREAK_EVENT` routinely no-ops for
+            // processes outside our console's process group, and neither it
+            // nor `WM_CLOSE` is guaranteed to actually terminate the
+            // process, hence `Config::escalation_timeout` existing at all.
+            // We can at least confirm the target existed when we tried.
+            request_graceful_shutdown(process_id);
+            return Ok(KillOutput::Killed { process_id });
+        }
         let result: Result<KillOutput>;
         unsafe {
             let open_result = OpenProcess(PROCESS_TERMINATE, false, process_id);
@@ -119,6 +324,8 @@ pub(crate) mod blocking {
                         process_entry.dwSize = process_entry_size;
                         match Process32First(snapshot_handle, &mut process_entry) {
                             Ok(()) => loop {
+                                let (uid, gid) =
+                                    super::get_owner_ids(process_entry.th32ProcessID);
                                 process_infos.push(ProcessInfo {
                                     process_id: process_entry.th32ProcessID,
                                     parent_process_id: process_entry.th32ParentProcessID,
@@ -127,6 +334,14 @@ pub(crate) mod blocking {
                                     )
                                     .to_string_lossy()
                                     .into_owned(),
+                                    start_time: super::get_start_time(
+                                        process_entry.th32ProcessID,
+                                    )
+                                    .unwrap_or_default(),
+                                    status: super::get_status(process_entry.th32ProcessID),
+                                    uid,
+                                    gid,
+                                    cmdline: super::get_cmdline(process_entry.th32ProcessID),
                                 });
                                 match Process32Next(snapshot_handle, &mut process_entry) {
                                     Ok(()) => {}
@@ -166,23 +381,41 @@ pub(crate) mod tokio {
     use super::*;
     use async_trait::async_trait;
 
+    pub(crate) use super::child_process_id_map_filter;
+    pub(crate) use super::is_alive;
+
     #[derive(Clone)]
     struct Killer {}
 
     #[async_trait]
     impl crate::tokio::Killable for Killer {
-        async fn kill(&self, process_id: ProcessId) -> Result<KillOutput> {
-            crate::windows::tokio::kill(process_id).await
+        async fn kill(
+            &self,
+            process_id: ProcessId,
+            signal: Signal,
+            expected_start_time: Option<u64>,
+        ) -> Result<KillOutput> {
+            crate::windows::tokio::kill(process_id, signal, expected_start_time).await
         }
     }
 
-    pub(crate) fn new_killer(_config: &Config) -> Result<impl crate::tokio::Killable> {
+    pub(crate) fn new_killer(
+        _config: &Config,
+        _process_ids: &[ProcessId],
+    ) -> Result<impl crate::tokio::Killable> {
         Ok(Killer {})
     }
 
     #[instrument]
-    async fn kill(process_id: ProcessId) -> Result<KillOutput> {
-        ::tokio::task::spawn_blocking(move || crate::windows::blocking::kill(process_id)).await?
+    async fn kill(
+        process_id: ProcessId,
+        signal: Signal,
+        expected_start_time: Option<u64>,
+    ) -> Result<KillOutput> {
+        ::tokio::task::spawn_blocking(move || {
+            crate::windows::blocking::kill(process_id, signal, expected_start_time)
+        })
+        .await?
     }
 
     #[instrument]